@@ -1,7 +1,69 @@
 use crate::logic::{
-    self, day_to_mat_type, group_by_material, mat_type_to_name, relevant_days, Character,
+    self, current_day_of_week, day_to_mat_type, days_until_farmable, group_by_material,
+    mat_type_to_name, relevant_days, Character, DayOfWeek, Language, TalentLevelUpMaterialType,
 };
 use leptos::*;
+use std::collections::HashMap;
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+use wasm_bindgen::JsCast;
+
+// The day-to-material mapping can fail to load (e.g. a malformed or missing
+// resources.json), so it's kept as a `Result` all the way to the view layer
+// instead of being unwrapped. The error is stringified because `anyhow::Error`
+// isn't `Clone`, which a `Resource`'s value must be.
+type DayToMatResult = Result<HashMap<DayOfWeek, Vec<TalentLevelUpMaterialType>>, String>;
+
+// Static UI chrome text, localized the same way `Language::display_name` is:
+// a plain match, since these strings have no entry in words.json to look up.
+fn title_text(language: Language) -> &'static str {
+    match language {
+        Language::En => "Genshin Talent Materials by Day",
+        Language::Ja => "原神曜日別素材",
+    }
+}
+
+fn countdown_available_text(language: Language) -> &'static str {
+    match language {
+        Language::En => "Available today",
+        Language::Ja => "本日取れます",
+    }
+}
+
+fn countdown_remaining_text(language: Language, distance: u32) -> String {
+    match language {
+        Language::En => format!("{} day(s) left", distance),
+        Language::Ja => format!("あと{}日", distance),
+    }
+}
+
+fn export_ics_label(language: Language) -> &'static str {
+    match language {
+        Language::En => "Export weekly schedule to calendar (.ics)",
+        Language::Ja => "週間予定をカレンダーに書き出す (.ics)",
+    }
+}
+
+fn copy_text_label(language: Language) -> &'static str {
+    match language {
+        Language::En => "Copy table (text)",
+        Language::Ja => "表をコピー (テキスト)",
+    }
+}
+
+fn copy_markdown_label(language: Language) -> &'static str {
+    match language {
+        Language::En => "Copy table (Markdown)",
+        Language::Ja => "表をコピー (Markdown)",
+    }
+}
+
+fn legend_text(language: Language) -> &'static str {
+    match language {
+        Language::En => "Materials farmable today have this background color",
+        Language::Ja => "本日取れる素材はこの背景色",
+    }
+}
 
 #[component]
 fn CharacterComponent(character: Character) -> impl IntoView {
@@ -19,8 +81,11 @@ fn CharacterComponent(character: Character) -> impl IntoView {
 
 #[component]
 fn MaterialsView(mat_type: logic::TalentLevelUpMaterialType) -> impl IntoView {
-    let all_characters = use_context::<Resource<(), Vec<Character>>>()
+    let language = use_context::<RwSignal<Language>>().expect("Language context must be provided.");
+    let all_characters = use_context::<Resource<Language, Vec<Character>>>()
         .expect("An anscestor must load all characters.");
+    let day_to_mat = use_context::<Resource<(), DayToMatResult>>()
+        .expect("An anscestor must load day_to_mat_type.");
     let characters = move || {
         let Some(characters) = all_characters.get() else {
             return vec![];
@@ -30,12 +95,26 @@ fn MaterialsView(mat_type: logic::TalentLevelUpMaterialType) -> impl IntoView {
         characters.unwrap_or_default()
     };
 
-    let mat_name = mat_type_to_name(mat_type).unwrap_or("".to_string());
+    let mat_name = move || mat_type_to_name(mat_type, language.get()).unwrap_or_default();
+    let countdown = move || {
+        let Some(Ok(day_to_mat)) = day_to_mat.get() else {
+            return String::new();
+        };
+        let distance = days_until_farmable(&day_to_mat, mat_type, current_day_of_week());
+        if distance == 0 {
+            countdown_available_text(language.get()).to_string()
+        } else {
+            countdown_remaining_text(language.get(), distance)
+        }
+    };
 
     view! {
         <div>
             <div class="text-warning">
-            {mat_name.clone()}
+            {mat_name}
+            </div>
+            <div class="text-muted small">
+            {countdown}
             </div>
         <Suspense
             fallback=move || view! { <p>"Loading..."</p> }
@@ -58,22 +137,26 @@ fn MaterialsView(mat_type: logic::TalentLevelUpMaterialType) -> impl IntoView {
 
 #[component]
 fn ShowByDayOfWeek(relevant_day: logic::RelevantDay) -> impl IntoView {
-    let day_to_mat = day_to_mat_type();
+    let day_to_mat = use_context::<Resource<(), DayToMatResult>>()
+        .expect("An anscestor must load day_to_mat_type.");
+    let day_of_week = relevant_day.day_of_week.clone();
 
-    let mat_types = day_to_mat
-        .get(&relevant_day.day_of_week)
-        .expect("All days exist");
-
-    let mat_views = mat_types
-        .iter()
-        .map(|mat_type| {
-            view! {
-                <div>
-                    <MaterialsView mat_type={*mat_type} />
-                </div>
-            }
-        })
-        .collect::<Vec<_>>();
+    let mat_views = move || {
+        let Some(Ok(day_to_mat)) = day_to_mat.get() else {
+            return vec![];
+        };
+        let mat_types = day_to_mat.get(&day_of_week).cloned().unwrap_or_default();
+        mat_types
+            .iter()
+            .map(|mat_type| {
+                view! {
+                    <div>
+                        <MaterialsView mat_type={*mat_type} />
+                    </div>
+                }
+            })
+            .collect::<Vec<_>>()
+    };
 
     view! {
         <div>
@@ -116,12 +199,145 @@ pub fn DisplayMats() -> impl IntoView {
     }
 }
 
+// Triggers a browser download of `contents` as a file named `filename`, via a
+// throwaway Blob + anchor click (there is no server to serve the file from).
+fn trigger_download(filename: &str, contents: &str, mime_type: &str) {
+    use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url};
+
+    let parts = js_sys::Array::new();
+    parts.push(&wasm_bindgen::JsValue::from_str(contents));
+
+    let mut options = BlobPropertyBag::new();
+    options.type_(mime_type);
+    let blob = Blob::new_with_str_sequence_and_options(&parts, &options)
+        .expect("failed to create blob");
+    let url = Url::create_object_url_with_blob(&blob).expect("failed to create object url");
+
+    let anchor = document()
+        .create_element("a")
+        .expect("failed to create anchor")
+        .dyn_into::<HtmlAnchorElement>()
+        .expect("created element is an anchor");
+    anchor.set_href(&url);
+    anchor.set_download(filename);
+    anchor.click();
+
+    Url::revoke_object_url(&url).ok();
+}
+
+// Copies `text` to the system clipboard. Fire-and-forget: the Clipboard API
+// is promise-based, but there is nothing useful to do with the result here.
+fn copy_to_clipboard(text: &str) {
+    let _ = window().navigator().clipboard().write_text(text);
+}
+
+#[component]
+fn ExportIcsButton() -> impl IntoView {
+    let language = use_context::<RwSignal<Language>>().expect("Language context must be provided.");
+    let all_characters = use_context::<Resource<Language, Vec<Character>>>()
+        .expect("An anscestor must load all characters.");
+    let day_to_mat = use_context::<Resource<(), DayToMatResult>>()
+        .expect("An anscestor must load day_to_mat_type.");
+
+    let on_click = move |_| {
+        let Some(characters) = all_characters.get() else {
+            return;
+        };
+        let Some(Ok(day_to_mat)) = day_to_mat.get() else {
+            return;
+        };
+        let mat_to_characters = group_by_material(characters);
+        match logic::ical::to_ical_calendar(&day_to_mat, &mat_to_characters, language.get()) {
+            Ok(ics) => trigger_download("genshin-talent-mats.ics", &ics, "text/calendar"),
+            Err(err) => leptos::logging::error!("failed to build iCalendar export: {err}"),
+        }
+    };
+
+    view! {
+        <button class="btn btn-outline-primary" on:click=on_click>
+            {move || export_ics_label(language.get())}
+        </button>
+    }
+}
+
+#[component]
+fn CopyTableButton() -> impl IntoView {
+    let language = use_context::<RwSignal<Language>>().expect("Language context must be provided.");
+    let all_characters = use_context::<Resource<Language, Vec<Character>>>()
+        .expect("An anscestor must load all characters.");
+    let day_to_mat = use_context::<Resource<(), DayToMatResult>>()
+        .expect("An anscestor must load day_to_mat_type.");
+
+    let copy = move |to_text: fn(
+        &HashMap<DayOfWeek, Vec<TalentLevelUpMaterialType>>,
+        &HashMap<TalentLevelUpMaterialType, Vec<Character>>,
+        Language,
+    ) -> anyhow::Result<String>| {
+        let Some(characters) = all_characters.get() else {
+            return;
+        };
+        let Some(Ok(day_to_mat)) = day_to_mat.get() else {
+            return;
+        };
+        let mat_to_characters = group_by_material(characters);
+        match to_text(&day_to_mat, &mat_to_characters, language.get()) {
+            Ok(text) => copy_to_clipboard(&text),
+            Err(err) => leptos::logging::error!("failed to build table export: {err}"),
+        }
+    };
+
+    view! {
+        <button
+            class="btn btn-outline-secondary"
+            on:click=move |_| copy(logic::text_table::to_plain_text_table)
+        >
+            {move || copy_text_label(language.get())}
+        </button>
+        <button
+            class="btn btn-outline-secondary"
+            on:click=move |_| copy(logic::text_table::to_markdown_table)
+        >
+            {move || copy_markdown_label(language.get())}
+        </button>
+    }
+}
+
+#[component]
+fn LanguagePicker() -> impl IntoView {
+    let language = use_context::<RwSignal<Language>>().expect("Language context must be provided.");
+
+    let on_change = move |ev| {
+        let value = event_target_value(&ev);
+        if let Ok(selected) = Language::from_str(&value) {
+            language.set(selected);
+        }
+    };
+
+    let options = Language::iter()
+        .map(|lang| {
+            view! {
+                <option value={lang.as_ref().to_string()} selected={language.get() == lang}>
+                    {lang.display_name()}
+                </option>
+            }
+        })
+        .collect::<Vec<_>>();
+
+    view! {
+        <select class="form-select" on:change=on_change>
+            {options}
+        </select>
+    }
+}
+
 #[component]
 fn TableLegend() -> impl IntoView {
+    let language = use_context::<RwSignal<Language>>().expect("Language context must be provided.");
+
     view! {
         <div>
             <div class="legend-today">
-            "本日取れる素材はこの背景色"
+            {move || legend_text(language.get())}
             </div>
         </div>
     }
@@ -129,19 +345,40 @@ fn TableLegend() -> impl IntoView {
 
 #[component]
 pub fn App() -> impl IntoView {
+    let language = create_rw_signal(Language::default());
+    provide_context(language);
+
     // Load up character info once here and provide as context. Then the
-    // descendant components don't need to re-load.
+    // descendant components don't need to re-load. Re-fetches whenever the
+    // selected language changes, since character names are localized.
     let characters = create_resource(
-        || (),
-        move |_| async move { logic::read_better_character_mats().unwrap_or_default() },
+        move || language.get(),
+        move |language| async move { logic::read_character_mats(language).unwrap_or_default() },
     );
     provide_context(characters);
 
+    // `day_to_mat_type` is fallible (e.g. a malformed resources.json), so it's
+    // loaded as a resource and rendered through a Suspense/error fallback
+    // rather than unwrapped.
+    let day_to_mat: Resource<(), DayToMatResult> =
+        create_resource(|| (), |_| async move { day_to_mat_type().map_err(|e| e.to_string()) });
+    provide_context(day_to_mat);
+
     view! {
-        <h1 class="fs-1">"原神曜日別素材"</h1>
+        <h1 class="fs-1">{move || title_text(language.get())}</h1>
+        <LanguagePicker />
         <TableLegend />
+        <ExportIcsButton />
+        <CopyTableButton />
         <div class="container">
-            <DisplayMats />
+            <Suspense fallback=move || view! { <p>"Loading..."</p> }>
+            {move || match day_to_mat.get() {
+                Some(Err(err)) => view! {
+                    <p class="text-danger">{format!("Failed to load material schedule: {}", err)}</p>
+                }.into_view(),
+                _ => view! { <DisplayMats /> }.into_view(),
+            }}
+            </Suspense>
         </div>
     }
 }
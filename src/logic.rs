@@ -6,6 +6,7 @@ use anyhow::Context;
 use anyhow::Result;
 use chrono::Datelike;
 use chrono::Local;
+use leptos::logging;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_data::WordEntry;
@@ -19,6 +20,8 @@ use strum_macros::EnumIter;
 use strum_macros::EnumString;
 
 mod asset;
+pub mod ical;
+pub mod text_table;
 
 const RESOURCES_FILE: &str = "resources.json";
 const LEVEL_UP_MAT: &str = "Talent Level-Up Material";
@@ -36,6 +39,49 @@ pub enum DayOfWeek {
     Sunday,
 }
 
+// Things to edit (in this file) when adding a new language.
+// - Add the variant here.
+// - Add its key to `character_name_key` (key into `CharacterEntry.names`).
+// - Add its key to `word_key` (key into `WordEntry`'s translation map).
+#[derive(
+    EnumIter, Debug, AsRefStr, EnumString, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash,
+)]
+pub enum Language {
+    En,
+    Ja,
+}
+
+impl Default for Language {
+    fn default() -> Self {
+        Language::Ja
+    }
+}
+
+impl Language {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Language::En => "English",
+            Language::Ja => "日本語",
+        }
+    }
+
+    // Key into `CharacterEntry.names`.
+    fn character_name_key(&self) -> &'static str {
+        match self {
+            Language::En => "EN",
+            Language::Ja => "JP",
+        }
+    }
+
+    // Key into `WordEntry`'s translation map.
+    fn word_key(&self) -> &'static str {
+        match self {
+            Language::En => "en",
+            Language::Ja => "ja",
+        }
+    }
+}
+
 #[derive(
     EnumIter,
     Debug,
@@ -154,12 +200,26 @@ mod serde_data {
 
     // For reading characters.json.
     // For words.json.
-    // For now, it is only used to go from English to Japanese.
     #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct WordEntry {
         pub en: String,
-        // Some entries do not have it, this must be Option.
-        pub ja: Option<String>,
+        // Translations keyed by language code (e.g. "ja"). Not all entries
+        // have a translation for every language.
+        #[serde(flatten)]
+        pub translations: HashMap<String, String>,
+    }
+
+    impl WordEntry {
+        // Looks up the translation for `language_key`, falling back to `en`.
+        pub fn translation(&self, language_key: &str) -> Option<&str> {
+            if language_key == "en" {
+                return Some(self.en.as_str());
+            }
+            self.translations
+                .get(language_key)
+                .map(|s| s.as_str())
+                .or(Some(self.en.as_str()))
+        }
     }
 }
 
@@ -177,19 +237,30 @@ impl Character {
     }
 }
 
-pub fn day_to_mat_type() -> HashMap<DayOfWeek, Vec<TalentLevelUpMaterialType>> {
+// Builds the day-of-week -> material-type mapping. Resources with a day or
+// material type this binary doesn't recognize yet (e.g. a brand new region)
+// are skipped with a logged warning instead of panicking the whole app.
+pub fn day_to_mat_type() -> Result<HashMap<DayOfWeek, Vec<TalentLevelUpMaterialType>>> {
     type MatType = TalentLevelUpMaterialType;
     let mut map = HashMap::new();
-    // TODO: Don't unwrap here in case of failure.
-    let resources = read_resources().unwrap();
+    let resources = read_resources()?;
     for (name, resource) in &resources {
         if !name.contains("Teachings of ") {
             continue;
         }
-        let days = resource.days.as_ref().unwrap();
+        let Some(mat_type) = MatType::from_full_name(name) else {
+            logging::warn!("skipping {name}: unrecognized material type");
+            continue;
+        };
+        let Some(days) = resource.days.as_ref() else {
+            logging::warn!("skipping {name}: no day-of-week data");
+            continue;
+        };
         for day in days {
-            let day = DayOfWeek::from_str(day).unwrap();
-            let mat_type = MatType::from_full_name(name).unwrap();
+            let Ok(day) = DayOfWeek::from_str(day) else {
+                logging::warn!("skipping {name}: unrecognized day of week {day}");
+                continue;
+            };
             map.entry(day).or_insert_with(Vec::new).push(mat_type);
         }
     }
@@ -199,7 +270,92 @@ pub fn day_to_mat_type() -> HashMap<DayOfWeek, Vec<TalentLevelUpMaterialType>> {
         values.sort();
         values.reverse();
     }
-    map
+    Ok(map)
+}
+
+// Monday=0..Sunday=6, mirroring chrono's `Weekday::num_days_from_monday`.
+fn day_index(day: &DayOfWeek) -> u32 {
+    match day {
+        DayOfWeek::Monday => 0,
+        DayOfWeek::Tuesday => 1,
+        DayOfWeek::Wednesday => 2,
+        DayOfWeek::Thursday => 3,
+        DayOfWeek::Friday => 4,
+        DayOfWeek::Saturday => 5,
+        DayOfWeek::Sunday => 6,
+    }
+}
+
+fn index_to_day(index: u32) -> DayOfWeek {
+    match index {
+        0 => DayOfWeek::Monday,
+        1 => DayOfWeek::Tuesday,
+        2 => DayOfWeek::Wednesday,
+        3 => DayOfWeek::Thursday,
+        4 => DayOfWeek::Friday,
+        5 => DayOfWeek::Saturday,
+        _ => DayOfWeek::Sunday,
+    }
+}
+
+// Forward distance (in days) from `today` to `avail`, wrapping within a week.
+fn forward_distance(today: u32, avail: u32) -> u32 {
+    let today = today as i64;
+    let avail = avail as i64;
+    ((avail - today + 7) % 7) as u32
+}
+
+pub fn current_day_of_week() -> DayOfWeek {
+    match Local::now().weekday() {
+        chrono::Weekday::Mon => DayOfWeek::Monday,
+        chrono::Weekday::Tue => DayOfWeek::Tuesday,
+        chrono::Weekday::Wed => DayOfWeek::Wednesday,
+        chrono::Weekday::Thu => DayOfWeek::Thursday,
+        chrono::Weekday::Fri => DayOfWeek::Friday,
+        chrono::Weekday::Sat => DayOfWeek::Saturday,
+        chrono::Weekday::Sun => DayOfWeek::Sunday,
+    }
+}
+
+// Number of days until `mat_type` is next farmable, counting from `today`.
+// Sunday is universal in this game (every talent material is farmable), so
+// it is always treated as available even if resources.json doesn't list it.
+// `day_to_mat` is taken by reference rather than loaded here so that callers
+// that already hold it (e.g. a `Resource` in the view layer) don't have to
+// re-read and re-parse resources.json on every call.
+pub fn days_until_farmable(
+    day_to_mat: &HashMap<DayOfWeek, Vec<TalentLevelUpMaterialType>>,
+    mat_type: TalentLevelUpMaterialType,
+    today: DayOfWeek,
+) -> u32 {
+    if today == DayOfWeek::Sunday {
+        return 0;
+    }
+
+    let today_idx = day_index(&today);
+    let mut avail_days: Vec<DayOfWeek> = day_to_mat
+        .iter()
+        .filter(|(_, mat_types)| mat_types.contains(&mat_type))
+        .map(|(day, _)| day.clone())
+        .collect();
+    avail_days.push(DayOfWeek::Sunday);
+
+    avail_days
+        .iter()
+        .map(|day| forward_distance(today_idx, day_index(day)))
+        .min()
+        .unwrap_or(0)
+}
+
+// The next day `mat_type` is farmable, counting from (and possibly equal to) `today`.
+pub fn next_farmable_day(
+    day_to_mat: &HashMap<DayOfWeek, Vec<TalentLevelUpMaterialType>>,
+    mat_type: TalentLevelUpMaterialType,
+    today: DayOfWeek,
+) -> DayOfWeek {
+    let distance = days_until_farmable(day_to_mat, mat_type, today.clone());
+    let next_idx = (day_index(&today) + distance) % 7;
+    index_to_day(next_idx)
 }
 
 pub fn group_by_material(
@@ -230,8 +386,8 @@ fn extract_str_in_ja_quotes(ja: &str) -> Option<&str> {
     s.chars().next().map(|c| &s[c.len_utf8()..])
 }
 
-// Returns the display name for mat_type.
-pub fn mat_type_to_name(mat_type: TalentLevelUpMaterialType) -> Result<String> {
+// Returns the display name for mat_type in the given language.
+pub fn mat_type_to_name(mat_type: TalentLevelUpMaterialType, language: Language) -> Result<String> {
     let words = read_words()?;
     let teaching = format!("Teachings of {}", mat_type.as_ref());
 
@@ -239,17 +395,22 @@ pub fn mat_type_to_name(mat_type: TalentLevelUpMaterialType) -> Result<String> {
         .get(&teaching)
         .with_context(|| format!("failed to find {}", &teaching))?;
 
-    let ja = entry
-        .ja
-        .as_ref()
-        .with_context(|| format!("No japanese translationf for {}", &teaching))?;
+    let translation = entry
+        .translation(language.word_key())
+        .with_context(|| format!("no translation for {}", &teaching))?;
 
-    let contains_ja_quotes = ja.contains("「") && ja.contains("」");
+    if language != Language::Ja {
+        return Ok(translation.to_owned());
+    }
+
+    // The Japanese material name is quoted inside the longer item description,
+    // e.g. 精霊の塵 「正義」の塵 -> 正義.
+    let contains_ja_quotes = translation.contains("「") && translation.contains("」");
     if !contains_ja_quotes {
         bail!("failed to find 「」in {}", &teaching);
     }
 
-    let ja = extract_str_in_ja_quotes(ja).context("failed to extract string")?;
+    let ja = extract_str_in_ja_quotes(translation).context("failed to extract string")?;
     Ok(ja.to_owned())
 }
 
@@ -266,43 +427,78 @@ fn material_name_to_day_of_week(
     resources: &HashMap<String, serde_data::ResourceEntry>,
 ) -> Option<Vec<DayOfWeek>> {
     // Now find the material name in resources to get all the day of week.
-    let days_of_week = resources.get(name).map(|resource| resource.days.as_ref())?;
+    let days_of_week = resources.get(name).map(|resource| resource.days.as_ref())??;
 
-    let days_of_week: Vec<DayOfWeek> = days_of_week?
+    let days_of_week: Vec<DayOfWeek> = days_of_week
         .iter()
-        .map(|day_of_week| DayOfWeek::from_str(day_of_week).unwrap())
+        .filter_map(|day_of_week| match DayOfWeek::from_str(day_of_week) {
+            Ok(day) => Some(day),
+            Err(_) => {
+                logging::warn!("skipping {name}: unrecognized day of week {day_of_week}");
+                None
+            }
+        })
         .collect();
 
     Some(days_of_week)
 }
 
-pub fn read_character_mats() -> Result<Vec<Character>> {
+pub fn read_character_mats(language: Language) -> Result<Vec<Character>> {
     let better_characters = read_better_characters()?;
     let resources = read_resources()?;
 
     let characters = better_characters.iter().filter_map(|better_character| {
-        const CHARACTER_NAME_LANGUAGE: &str = "JP";
         if better_character.name == "Traveler" {
             return None;
         }
-        let name = better_character.names[CHARACTER_NAME_LANGUAGE].clone();
+        let Some(name) = better_character
+            .names
+            .get(language.character_name_key())
+            .cloned()
+        else {
+            logging::warn!(
+                "skipping {}: no name for language {:?}",
+                &better_character.name,
+                language
+            );
+            return None;
+        };
         let thumbnail = format!(
             "Character_{}_Thumb.webp",
             &better_character.name.replace(" ", "_")
         );
 
+        // Materials with an unrecognized type or no known day-of-week data are
+        // dropped instead of panicking the whole app (e.g. a region HoYoverse
+        // shipped after this binary was built).
         let talent_materials = better_character
             .talent_materials
             .iter()
-            .map(|talent_material| {
-                let mat_type =
-                    TalentLevelUpMaterialType::from_full_name(&talent_material.name).unwrap();
-                let days = material_name_to_day_of_week(&talent_material.name, &resources).unwrap();
-                TalentLevelUpMaterial {
+            .filter_map(|talent_material| {
+                let Some(mat_type) =
+                    TalentLevelUpMaterialType::from_full_name(&talent_material.name)
+                else {
+                    logging::warn!(
+                        "skipping {} for {}: unrecognized material type",
+                        &talent_material.name,
+                        &better_character.name
+                    );
+                    return None;
+                };
+                let Some(days) = material_name_to_day_of_week(&talent_material.name, &resources)
+                else {
+                    logging::warn!(
+                        "skipping {} for {}: no day-of-week data",
+                        &talent_material.name,
+                        &better_character.name
+                    );
+                    return None;
+                };
+                Some(TalentLevelUpMaterial {
                     name: talent_material.name.clone(),
                     mat_type,
                     days,
-                }
+                })
             })
             .collect();
         Some(Character::new(name, talent_materials, thumbnail))
@@ -348,16 +544,7 @@ pub struct RelevantDay {
 }
 
 pub fn relevant_days() -> Vec<RelevantDay> {
-    let weekday = Local::now().weekday();
-    let weekday = match weekday {
-        chrono::Weekday::Mon => DayOfWeek::Monday,
-        chrono::Weekday::Tue => DayOfWeek::Tuesday,
-        chrono::Weekday::Wed => DayOfWeek::Wednesday,
-        chrono::Weekday::Thu => DayOfWeek::Thursday,
-        chrono::Weekday::Fri => DayOfWeek::Friday,
-        chrono::Weekday::Sat => DayOfWeek::Saturday,
-        chrono::Weekday::Sun => DayOfWeek::Sunday,
-    };
+    let weekday = current_day_of_week();
     vec![
         RelevantDay {
             day_of_week: DayOfWeek::Monday,
@@ -403,7 +590,7 @@ mod tests {
     // Cannot check too much, as it requires checking implementation details.
     #[test]
     fn test_read_character_mats() -> Result<()> {
-        let characters = read_character_mats().unwrap();
+        let characters = read_character_mats(Language::Ja).unwrap();
         assert_ge!(characters.len(), 1);
         let furina = characters
             .iter()
@@ -420,7 +607,7 @@ mod tests {
     fn test_group_by_material() -> Result<()> {
         // TODO: Prefer self contained tests. Don't read from data set here. Instead create a
         // toy dataset in this test.
-        let characters = read_character_mats().unwrap();
+        let characters = read_character_mats(Language::Ja).unwrap();
         let group = group_by_material(characters);
         assert_ge!(group.len(), 1);
 
@@ -442,15 +629,23 @@ mod tests {
 
     #[test]
     fn test_mat_type_to_name() -> Result<()> {
-        let justice = mat_type_to_name(TalentLevelUpMaterialType::Justice)?;
+        let justice = mat_type_to_name(TalentLevelUpMaterialType::Justice, Language::Ja)?;
         assert_eq!("正義", justice);
 
         Ok(())
     }
 
+    #[test]
+    fn test_mat_type_to_name_english() -> Result<()> {
+        let justice = mat_type_to_name(TalentLevelUpMaterialType::Justice, Language::En)?;
+        assert_eq!("Teachings of Justice", justice);
+
+        Ok(())
+    }
+
     #[test]
     fn test_day_to_mat_type() -> Result<()> {
-        let day_to_mat = day_to_mat_type();
+        let day_to_mat = day_to_mat_type()?;
         assert_ge!(day_to_mat.len(), 1);
 
         // Make sure there are entries for all day of the week.
@@ -471,4 +666,63 @@ mod tests {
         assert!(found.is_some());
         Ok(())
     }
+
+    #[test]
+    fn test_days_until_farmable() -> Result<()> {
+        let day_to_mat = day_to_mat_type()?;
+        // Contention is farmable on Monday (see test_day_to_mat_type).
+        assert_eq!(
+            days_until_farmable(
+                &day_to_mat,
+                TalentLevelUpMaterialType::Contention,
+                DayOfWeek::Monday
+            ),
+            0
+        );
+        assert_eq!(
+            days_until_farmable(
+                &day_to_mat,
+                TalentLevelUpMaterialType::Contention,
+                DayOfWeek::Saturday
+            ),
+            1
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_days_until_farmable_sunday_is_always_zero() -> Result<()> {
+        let day_to_mat = day_to_mat_type()?;
+        assert_eq!(
+            days_until_farmable(
+                &day_to_mat,
+                TalentLevelUpMaterialType::Contention,
+                DayOfWeek::Sunday
+            ),
+            0
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_farmable_day() -> Result<()> {
+        let day_to_mat = day_to_mat_type()?;
+        assert_eq!(
+            next_farmable_day(
+                &day_to_mat,
+                TalentLevelUpMaterialType::Contention,
+                DayOfWeek::Saturday
+            ),
+            DayOfWeek::Sunday
+        );
+        assert_eq!(
+            next_farmable_day(
+                &day_to_mat,
+                TalentLevelUpMaterialType::Contention,
+                DayOfWeek::Monday
+            ),
+            DayOfWeek::Monday
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,197 @@
+// Renders the day-to-material schedule as an RFC5545 iCalendar feed so that
+// users can subscribe to it instead of checking the table every day.
+
+use super::mat_type_to_name;
+use super::Character;
+use super::DayOfWeek;
+use super::Language;
+use super::TalentLevelUpMaterialType;
+use anyhow::Result;
+use std::collections::HashMap;
+
+const PRODID: &str = "-//genshin-mats-table//Weekly Talent Materials//EN";
+// Arbitrary Monday used as the DTSTART anchor for every weekly RRULE.
+const DTSTART_ANCHOR: &str = "20240101T090000";
+const DTEND_ANCHOR: &str = "20240101T100000";
+// DTSTAMP is required on every VEVENT (RFC5545 3.6.1); this feed has no
+// meaningful "generated at" time, so it uses midnight UTC on the DTSTART
+// anchor's date.
+const DTSTAMP_ANCHOR: &str = "20240101T000000Z";
+
+fn day_to_ical_code(day: &DayOfWeek) -> &'static str {
+    match day {
+        DayOfWeek::Monday => "MO",
+        DayOfWeek::Tuesday => "TU",
+        DayOfWeek::Wednesday => "WE",
+        DayOfWeek::Thursday => "TH",
+        DayOfWeek::Friday => "FR",
+        DayOfWeek::Saturday => "SA",
+        DayOfWeek::Sunday => "SU",
+    }
+}
+
+// Escapes TEXT values per RFC5545 3.3.11 (backslash, comma, semicolon, newline).
+fn escape_text(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// Folds a content line at 75 octets, continuing on the next physical line
+// prefixed with a single space, per RFC5545 3.1.
+fn fold_line(line: &str) -> String {
+    const LIMIT: usize = 75;
+    if line.len() <= LIMIT {
+        return line.to_string();
+    }
+
+    let mut folded = String::new();
+    let mut octets_on_line = 0;
+    for ch in line.chars() {
+        let ch_len = ch.len_utf8();
+        if octets_on_line + ch_len > LIMIT {
+            folded.push_str("\r\n ");
+            // The continuation line already starts with the mandatory single
+            // space, which counts against its own 75-octet budget.
+            octets_on_line = 1;
+        }
+        folded.push(ch);
+        octets_on_line += ch_len;
+    }
+    folded
+}
+
+fn property(name: &str, value: &str) -> String {
+    fold_line(&format!("{}:{}", name, escape_text(value)))
+}
+
+// Like `property`, but for values that are structural (RECUR, DATE-TIME, ...)
+// rather than the TEXT value type, so they must not be TEXT-escaped.
+fn raw_property(name: &str, value: &str) -> String {
+    fold_line(&format!("{}:{}", name, value))
+}
+
+fn build_vevent(
+    mat_type: TalentLevelUpMaterialType,
+    days: &[DayOfWeek],
+    characters: &[Character],
+    language: Language,
+) -> Result<String> {
+    let name = mat_type_to_name(mat_type, language)?;
+
+    // Sunday is universal in-game (every talent material is farmable), so it
+    // is always added to the recurrence regardless of what resources.json says.
+    let mut days: Vec<DayOfWeek> = days.to_vec();
+    days.push(DayOfWeek::Sunday);
+    days.sort_by_key(|day| super::day_index(day));
+    days.dedup();
+    let codes: Vec<&'static str> = days.iter().map(day_to_ical_code).collect();
+
+    let character_names = characters
+        .iter()
+        .map(|character| character.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut lines = vec![
+        "BEGIN:VEVENT".to_string(),
+        raw_property("UID", &format!("{}@genshin-mats-table", mat_type.as_ref())),
+        format!("DTSTAMP:{}", DTSTAMP_ANCHOR),
+        format!("DTSTART:{}", DTSTART_ANCHOR),
+        format!("DTEND:{}", DTEND_ANCHOR),
+        raw_property("RRULE", &format!("FREQ=WEEKLY;BYDAY={}", codes.join(","))),
+        property("SUMMARY", &name),
+    ];
+    if !character_names.is_empty() {
+        lines.push(property("DESCRIPTION", &character_names));
+    }
+    lines.push("END:VEVENT".to_string());
+
+    Ok(lines.join("\r\n"))
+}
+
+// Turns the day-to-material mapping (joined with the material-to-character
+// grouping) into an RFC5545 iCalendar string with one VEVENT per material
+// type, recurring weekly on the days it is actually farmable.
+pub fn to_ical_calendar(
+    day_to_mat: &HashMap<DayOfWeek, Vec<TalentLevelUpMaterialType>>,
+    mat_to_characters: &HashMap<TalentLevelUpMaterialType, Vec<Character>>,
+    language: Language,
+) -> Result<String> {
+    let mut mat_to_days: HashMap<TalentLevelUpMaterialType, Vec<DayOfWeek>> = HashMap::new();
+    for (day, mat_types) in day_to_mat {
+        for mat_type in mat_types {
+            mat_to_days
+                .entry(*mat_type)
+                .or_default()
+                .push(day.clone());
+        }
+    }
+
+    let mut mat_types: Vec<TalentLevelUpMaterialType> = mat_to_days.keys().copied().collect();
+    mat_types.sort();
+
+    let mut events = Vec::with_capacity(mat_types.len());
+    for mat_type in mat_types {
+        let days = mat_to_days.get(&mat_type).cloned().unwrap_or_default();
+        let characters = mat_to_characters
+            .get(&mat_type)
+            .cloned()
+            .unwrap_or_default();
+        events.push(build_vevent(mat_type, &days, &characters, language)?);
+    }
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        property("PRODID", PRODID),
+    ];
+    lines.extend(events);
+    lines.push("END:VCALENDAR".to_string());
+
+    Ok(lines.join("\r\n") + "\r\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_ical_calendar_adds_sunday_to_every_byday() -> Result<()> {
+        let mut day_to_mat = HashMap::new();
+        day_to_mat.insert(
+            DayOfWeek::Monday,
+            vec![TalentLevelUpMaterialType::Justice],
+        );
+        day_to_mat.insert(
+            DayOfWeek::Thursday,
+            vec![TalentLevelUpMaterialType::Justice],
+        );
+
+        let ics = to_ical_calendar(&day_to_mat, &HashMap::new(), Language::Ja)?;
+        assert!(ics.contains("BEGIN:VCALENDAR"));
+        assert!(ics.contains("END:VCALENDAR"));
+        assert!(ics.contains("RRULE:FREQ=WEEKLY;BYDAY=MO,TH,SU"));
+        assert!(ics.contains("DTSTAMP:"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_fold_line_wraps_long_lines() {
+        // Long enough that the first continuation line is itself a full 75
+        // octets, which is where the leading continuation space used to be
+        // left off the budget.
+        let long_value = "a".repeat(200);
+        let folded = fold_line(&format!("SUMMARY:{}", long_value));
+        assert!(folded.contains("\r\n "));
+        for line in folded.split("\r\n") {
+            assert!(line.len() <= 75);
+        }
+    }
+
+    #[test]
+    fn test_escape_text_escapes_special_characters() {
+        assert_eq!(escape_text("a,b;c\\d\ne"), "a\\,b\\;c\\\\d\\ne");
+    }
+}
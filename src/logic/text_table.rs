@@ -0,0 +1,206 @@
+// Renders the full day-group -> material -> character schedule as a plain
+// text table and a Markdown-pipe variant, for pasting into Discord, a wiki,
+// or a notes app (an export format independent of the HTML/CSS layout).
+
+use super::relevant_days;
+use super::mat_type_to_name;
+use super::Character;
+use super::DayOfWeek;
+use super::Language;
+use super::TalentLevelUpMaterialType;
+use anyhow::Result;
+use std::collections::HashMap;
+
+struct Table {
+    headers: Vec<String>,
+    // Each row is [material name, one cell per day-group column].
+    rows: Vec<Vec<String>>,
+}
+
+// Localized header for the material-name column.
+fn material_header(language: Language) -> &'static str {
+    match language {
+        Language::En => "Material",
+        Language::Ja => "素材",
+    }
+}
+
+fn build_table(
+    day_to_mat: &HashMap<DayOfWeek, Vec<TalentLevelUpMaterialType>>,
+    mat_to_characters: &HashMap<TalentLevelUpMaterialType, Vec<Character>>,
+    language: Language,
+) -> Result<Table> {
+    let groups = relevant_days();
+
+    let mut headers = vec![material_header(language).to_string()];
+    headers.extend(groups.iter().map(|group| group.display_name.clone()));
+
+    let mut rows = Vec::new();
+    for group in &groups {
+        let mat_types = day_to_mat.get(&group.day_of_week).cloned().unwrap_or_default();
+        for mat_type in mat_types {
+            let mut row = vec![mat_type_to_name(mat_type, language)?];
+            for other_group in &groups {
+                let cell = if other_group.day_of_week == group.day_of_week {
+                    mat_to_characters
+                        .get(&mat_type)
+                        .map(|characters| {
+                            characters
+                                .iter()
+                                .map(|character| character.name.as_str())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        })
+                        .unwrap_or_default()
+                } else {
+                    String::new()
+                };
+                row.push(cell);
+            }
+            rows.push(row);
+        }
+    }
+
+    Ok(Table { headers, rows })
+}
+
+// Column width in characters (not UTF-8 bytes), so padding is based on how
+// many glyphs a cell holds rather than how many bytes its CJK text takes up.
+fn column_widths(table: &Table) -> Vec<usize> {
+    let mut widths: Vec<usize> = table.headers.iter().map(|h| h.chars().count()).collect();
+    for row in &table.rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+    widths
+}
+
+fn pad(cell: &str, width: usize) -> String {
+    let padding = width.saturating_sub(cell.chars().count());
+    format!("{}{}", cell, " ".repeat(padding))
+}
+
+fn render_row(cells: &[String], widths: &[usize]) -> String {
+    cells
+        .iter()
+        .zip(widths)
+        .map(|(cell, width)| pad(cell, *width))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+fn render_plain_text(table: &Table) -> String {
+    let widths = column_widths(table);
+
+    let mut lines = vec![render_row(&table.headers, &widths)];
+    let separator = widths
+        .iter()
+        .map(|width| "-".repeat(*width))
+        .collect::<Vec<_>>()
+        .join("-+-");
+    lines.push(separator);
+    for row in &table.rows {
+        lines.push(render_row(row, &widths));
+    }
+
+    lines.join("\n")
+}
+
+// Escapes a literal pipe so it can't be mistaken for a column delimiter.
+fn escape_markdown_cell(cell: &str) -> String {
+    cell.replace('|', "\\|")
+}
+
+fn render_markdown(table: &Table) -> String {
+    let escaped_headers: Vec<String> = table.headers.iter().map(|h| escape_markdown_cell(h)).collect();
+    let mut lines = vec![format!("| {} |", escaped_headers.join(" | "))];
+    let separator = table
+        .headers
+        .iter()
+        .map(|_| "---")
+        .collect::<Vec<_>>()
+        .join(" | ");
+    lines.push(format!("| {} |", separator));
+    for row in &table.rows {
+        let escaped_row: Vec<String> = row.iter().map(|cell| escape_markdown_cell(cell)).collect();
+        lines.push(format!("| {} |", escaped_row.join(" | ")));
+    }
+
+    lines.join("\n")
+}
+
+// Monospace-aligned plain text rendering of the weekly schedule.
+pub fn to_plain_text_table(
+    day_to_mat: &HashMap<DayOfWeek, Vec<TalentLevelUpMaterialType>>,
+    mat_to_characters: &HashMap<TalentLevelUpMaterialType, Vec<Character>>,
+    language: Language,
+) -> Result<String> {
+    let table = build_table(day_to_mat, mat_to_characters, language)?;
+    Ok(render_plain_text(&table))
+}
+
+// Markdown-pipe-table rendering of the weekly schedule.
+pub fn to_markdown_table(
+    day_to_mat: &HashMap<DayOfWeek, Vec<TalentLevelUpMaterialType>>,
+    mat_to_characters: &HashMap<TalentLevelUpMaterialType, Vec<Character>>,
+    language: Language,
+) -> Result<String> {
+    let table = build_table(day_to_mat, mat_to_characters, language)?;
+    Ok(render_markdown(&table))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::Character;
+
+    fn sample_data() -> (
+        HashMap<DayOfWeek, Vec<TalentLevelUpMaterialType>>,
+        HashMap<TalentLevelUpMaterialType, Vec<Character>>,
+    ) {
+        let mut day_to_mat = HashMap::new();
+        day_to_mat.insert(DayOfWeek::Monday, vec![TalentLevelUpMaterialType::Justice]);
+
+        let mut mat_to_characters = HashMap::new();
+        mat_to_characters.insert(
+            TalentLevelUpMaterialType::Justice,
+            vec![Character::new("フリーナ".to_string(), vec![], "".to_string())],
+        );
+
+        (day_to_mat, mat_to_characters)
+    }
+
+    #[test]
+    fn test_to_plain_text_table_aligns_columns() -> Result<()> {
+        let (day_to_mat, mat_to_characters) = sample_data();
+        let table = to_plain_text_table(&day_to_mat, &mat_to_characters, Language::Ja)?;
+
+        let lines: Vec<&str> = table.lines().collect();
+        assert!(lines.len() >= 2);
+        // Every row should have the same rendered width in characters.
+        let widths: Vec<usize> = lines.iter().map(|line| line.chars().count()).collect();
+        assert_eq!(widths.iter().min(), widths.iter().max());
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_markdown_table_contains_pipes() -> Result<()> {
+        let (day_to_mat, mat_to_characters) = sample_data();
+        let table = to_markdown_table(&day_to_mat, &mat_to_characters, Language::Ja)?;
+
+        assert!(table.contains("| 素材 |"));
+        assert!(table.contains("フリーナ"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_markdown_table_localizes_header_for_english() -> Result<()> {
+        let (day_to_mat, mat_to_characters) = sample_data();
+        let table = to_markdown_table(&day_to_mat, &mat_to_characters, Language::En)?;
+
+        assert!(table.contains("| Material |"));
+        assert!(!table.contains("素材"));
+        Ok(())
+    }
+}